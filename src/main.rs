@@ -1,17 +1,22 @@
+use infra::shell::aliases;
+use infra::shell::history_search::{self, FuzzyHistorySearch};
+use infra::shell::snippets::{self, SnippetLibrary};
 use infra::shell::ShellCommandDetector;
 use infra::shell::ShellCommandExecutor;
 use rustyline::completion::Candidate;
 
 use std::cell::RefCell;
 use std::env;
+use std::fs;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use hostname::get;
 use rustyline::completion::{Completer, Pair};
 use rustyline::highlight::Highlighter;
 use rustyline::hint::Hinter;
 use rustyline::validate::{ValidationContext, ValidationResult, Validator};
-use rustyline::{Context, Editor, Helper};
+use rustyline::{Config, Context, Editor, EventHandler, Helper, KeyEvent};
 
 struct ShellCommandHinter {
     detector: Rc<RefCell<ShellCommandDetector>>,
@@ -55,12 +60,113 @@ impl Completer for InputHelper {
 
     fn complete(
         &self,
-        _line: &str,
-        _pos: usize,
+        line: &str,
+        pos: usize,
         _ctx: &Context<'_>,
     ) -> Result<(usize, Vec<Pair>), rustyline::error::ReadlineError> {
-        Ok((0, Vec::new()))
+        let start = line[..pos]
+            .rfind(char::is_whitespace)
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let token = &line[start..pos];
+        let is_first_token = line[last_segment_start(&line[..start])..start]
+            .trim()
+            .is_empty();
+        let looks_like_path =
+            token.starts_with("./") || token.starts_with('/') || token.starts_with('~');
+
+        let mut candidates = if is_first_token && !looks_like_path {
+            self.hinter
+                .detector
+                .borrow()
+                .command_completions(token)
+                .into_iter()
+                .map(|name| Pair {
+                    display: name.clone(),
+                    replacement: name,
+                })
+                .collect()
+        } else {
+            complete_path(token)
+        };
+        candidates.sort_by(|a, b| a.replacement().cmp(b.replacement()));
+
+        Ok((start, candidates))
+    }
+}
+
+/// Index just past the last top-level `|`, `&&`, `||` or `;` in `text`,
+/// skipping anything inside quotes; `0` if there isn't one. A pipe or
+/// connector starts a fresh command position, so completion needs to know
+/// where the *current segment* begins, not just the start of the line.
+fn last_segment_start(text: &str) -> usize {
+    let mut in_quote = None;
+    let mut boundary = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match in_quote {
+            Some(q) if c == q => in_quote = None,
+            Some(_) => {}
+            None if c == '\'' || c == '"' => in_quote = Some(c),
+            None if c == ';' => boundary = i + 1,
+            None if c == '|' && chars.peek().map(|&(_, next)| next) == Some('|') => {
+                let (j, _) = chars.next().unwrap();
+                boundary = j + 1;
+            }
+            None if c == '|' => boundary = i + 1,
+            None if c == '&' && chars.peek().map(|&(_, next)| next) == Some('&') => {
+                let (j, _) = chars.next().unwrap();
+                boundary = j + 1;
+            }
+            None => {}
+        }
     }
+
+    boundary
+}
+
+/// Lists entries of the directory named by `token`'s path portion whose
+/// names share `token`'s final segment as a prefix, appending `/` to
+/// directory matches.
+fn complete_path(token: &str) -> Vec<Pair> {
+    let (dir_prefix, file_prefix) = match token.rfind('/') {
+        Some(idx) => (&token[..=idx], &token[idx + 1..]),
+        None => ("", token),
+    };
+
+    let dir_to_read = if let Some(rest) = dir_prefix.strip_prefix("~/") {
+        let home = env::var("HOME").unwrap_or_default();
+        format!("{home}/{rest}")
+    } else if dir_prefix.is_empty() {
+        ".".to_string()
+    } else {
+        dir_prefix.to_string()
+    };
+
+    let entries = match std::fs::read_dir(&dir_to_read) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(file_prefix) {
+                return None;
+            }
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            let mut full = format!("{dir_prefix}{name}");
+            if is_dir {
+                full.push('/');
+            }
+            Some(Pair {
+                display: full.clone(),
+                replacement: full,
+            })
+        })
+        .collect()
 }
 
 impl Highlighter for InputHelper {
@@ -88,8 +194,10 @@ fn print_help() {
 }
 
 fn main() -> rustyline::Result<()> {
-    let detector = Rc::new(RefCell::new(ShellCommandDetector::new()));
-    let mut executor = ShellCommandExecutor::new();
+    let user_aliases = aliases::load_user_aliases();
+    let detector = Rc::new(RefCell::new(ShellCommandDetector::new(&user_aliases)));
+    let mut executor = ShellCommandExecutor::new(&user_aliases);
+    let snippet_library = SnippetLibrary::load_default();
 
     let helper = InputHelper {
         hinter: ShellCommandHinter {
@@ -97,14 +205,40 @@ fn main() -> rustyline::Result<()> {
         },
     };
 
-    let mut rl = Editor::new()?;
+    let config = Config::builder()
+        .max_history_size(1000)?
+        .history_ignore_dups(true)?
+        .build();
+    let mut rl = Editor::with_config(config)?;
     rl.set_helper(Some(helper));
+
+    let history_path = history_search::default_history_path();
+    if let Some(parent) = history_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = rl.load_history(&history_path);
+
+    let shell_history = executor.shell_history();
+    if let Ok(contents) = fs::read_to_string(&history_path) {
+        shell_history
+            .lock()
+            .unwrap()
+            .extend(contents.lines().map(|line| line.to_string()));
+    }
+    rl.bind_sequence(
+        KeyEvent::ctrl('R'),
+        EventHandler::Conditional(Box::new(FuzzyHistorySearch::new(Arc::clone(
+            &shell_history,
+        )))),
+    );
+
     println!("Enter commands to check (type 'exit' to quit):");
 
     let username = env::var("USER").unwrap_or_else(|_| "user".to_string());
     let hostname = get().unwrap_or_default().into_string().unwrap_or_default();
 
     loop {
+        executor.reap_finished_jobs();
         let current_dir = executor.get_current_dir();
         // ANSI color codes:
         // Red: \x1b[31m
@@ -144,11 +278,17 @@ fn main() -> rustyline::Result<()> {
             println!("\n\x1b[32mIt Is a shell command; \x1b[0m\n");
             let _ = rl.add_history_entry(input);
             executor.execute_shell_command(input.to_string());
+        } else if let Some(snippet) = snippets::select_snippet(&snippet_library, input) {
+            let resolved = snippets::resolve_snippet(snippet);
+            println!("\n\x1b[32mRunning:\x1b[0m {resolved}\n");
+            let _ = rl.add_history_entry(&resolved);
+            executor.execute_shell_command(resolved);
         } else {
             println!("\n\x1b[31mIt Is not a shell command; \x1b[0m\n");
         }
     }
 
+    let _ = rl.save_history(&history_path);
     println!("Goodbye!");
     Ok(())
 }
@@ -0,0 +1,41 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::env;
+use std::process::Command;
+
+/// Runs the user's interactive shell's `alias` builtin and parses its
+/// `alias name=value` lines. Shared by `ShellCommandDetector` (which only
+/// needs the names, to recognize aliases as valid commands) and
+/// `ShellCommandExecutor` (which needs the expansions too), so aliases are
+/// only queried from the shell once at startup rather than once per caller.
+pub fn load_user_aliases() -> Vec<(String, String)> {
+    let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
+
+    let Ok(output) = Command::new(&shell).args(["-i", "-c", "alias"]).output() else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_alias)
+        .collect()
+}
+
+/// Parses a single `alias name=value` line as emitted by the shell's `alias`
+/// builtin; `value` has any surrounding quotes stripped.
+fn parse_alias(line: &str) -> Option<(String, String)> {
+    static ALIAS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"alias\s+([^=]+)=(.*)").unwrap());
+
+    let caps = ALIAS_RE.captures(line)?;
+    let name = caps.get(1)?.as_str().trim().to_string();
+    let value = caps
+        .get(2)?
+        .as_str()
+        .trim()
+        .trim_matches(|c| c == '\'' || c == '"')
+        .to_string();
+    Some((name, value))
+}
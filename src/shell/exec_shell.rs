@@ -1,23 +1,82 @@
+use std::collections::{BTreeMap, HashSet};
 use std::env;
+use std::fs::{File, OpenOptions};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Child, Command, Stdio};
 use std::str::SplitWhitespace;
+use std::sync::{Arc, Mutex};
+
+/// A single command within a pipeline, along with any redirections that
+/// apply to it specifically (e.g. `cmd < in.txt | cmd2 > out.txt`).
+struct ParsedCommand {
+    program: String,
+    args: Vec<String>,
+    stdin_file: Option<String>,
+    stdout_file: Option<(String, bool)>, // (path, append)
+}
+
+/// How a pipeline is joined to the one that precedes it.
+enum Connector {
+    And, // &&
+    Or,  // ||
+}
+
+/// A background job spawned with a trailing `&`. `status` stays `None` while
+/// the process is still running. `children` holds every stage of the
+/// pipeline, in order, so earlier stages get `wait()`-ed (and thus reaped)
+/// rather than just the last one.
+struct Job {
+    id: usize,
+    command: String,
+    children: Vec<Child>,
+    status: Option<i32>,
+}
 
 pub struct ShellCommandExecutor {
     current_dir: String,
-    shell_history: Vec<String>,
+    shell_history: Arc<Mutex<Vec<String>>>,
+    env: BTreeMap<String, String>,
+    aliases: BTreeMap<String, String>,
+    jobs: Vec<Job>,
+    next_job_id: usize,
 }
 
 impl ShellCommandExecutor {
-    pub fn new() -> Self {
+    /// `user_aliases` is queried from the shell once at startup (by the
+    /// caller) and handed to every component that needs it, rather than each
+    /// one spawning its own interactive shell to ask.
+    pub fn new(user_aliases: &[(String, String)]) -> Self {
         let mut executor = ShellCommandExecutor {
             current_dir: String::new(),
-            shell_history: Vec::new(),
+            shell_history: Arc::new(Mutex::new(Vec::new())),
+            env: env::vars().collect(),
+            aliases: BTreeMap::new(),
+            jobs: Vec::new(),
+            next_job_id: 1,
         };
         executor.get_current_dir();
+        executor.load_user_aliases(user_aliases);
+        executor.env.insert("status".to_string(), "0".to_string());
         executor
     }
 
+    /// A shared handle onto the executor's command history, for callers
+    /// (e.g. a Ctrl-R fuzzy search binding) that need to read it without
+    /// holding a `&ShellCommandExecutor` borrow, and for seeding/persisting
+    /// it to disk across sessions.
+    pub fn shell_history(&self) -> Arc<Mutex<Vec<String>>> {
+        Arc::clone(&self.shell_history)
+    }
+
+    /// Appends `entry` to the shell history unless it repeats the last one.
+    fn record_history(&self, entry: String) {
+        let mut history = self.shell_history.lock().unwrap();
+        if history.last().is_some_and(|last| *last == entry) {
+            return;
+        }
+        history.push(entry);
+    }
+
     pub fn get_current_dir(&mut self) -> String {
         let current_dir = env::current_dir().unwrap_or_else(|_| ".".into());
         match current_dir.to_str() {
@@ -29,35 +88,498 @@ impl ShellCommandExecutor {
         };
     }
 
+    fn load_user_aliases(&mut self, user_aliases: &[(String, String)]) {
+        for (name, value) in user_aliases {
+            self.aliases.insert(name.clone(), value.clone());
+        }
+    }
+
+    /// Rewrites the leading word of `input` against the alias table, following
+    /// chained aliases until none match. A visited set stops `alias a=b; alias b=a`
+    /// style loops from expanding forever.
+    fn expand_aliases(&self, input: &str) -> String {
+        let mut current = input.to_string();
+        let mut visited = HashSet::new();
+
+        loop {
+            let mut parts = current.splitn(2, char::is_whitespace);
+            let first = parts.next().unwrap_or("").to_string();
+            let rest = parts.next().unwrap_or("");
+
+            match self.aliases.get(&first) {
+                Some(expansion) if visited.insert(first) => {
+                    current = if rest.is_empty() {
+                        expansion.clone()
+                    } else {
+                        format!("{} {}", expansion, rest)
+                    };
+                }
+                _ => break,
+            }
+        }
+
+        current
+    }
+
+    /// Replaces `$VAR` and `${VAR}` tokens with values from the in-process
+    /// environment map, leaving unknown variables as an empty string.
+    /// Expansion is skipped inside single-quoted spans, matching shell
+    /// semantics (`'$HOME'` stays literal); double-quoted and bare `$VAR`
+    /// still expand.
+    fn expand_variables(&self, input: &str) -> String {
+        let mut result = String::new();
+        let mut chars = input.chars().peekable();
+        let mut in_quote: Option<char> = None;
+
+        while let Some(c) = chars.next() {
+            match in_quote {
+                Some(q) if c == q => {
+                    in_quote = None;
+                    result.push(c);
+                }
+                Some('\'') => result.push(c),
+                None if c == '\'' || c == '"' => {
+                    in_quote = Some(c);
+                    result.push(c);
+                }
+                _ if c != '$' => result.push(c),
+                _ if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    let mut name = String::new();
+                    for c in chars.by_ref() {
+                        if c == '}' {
+                            break;
+                        }
+                        name.push(c);
+                    }
+                    if let Some(value) = self.env.get(&name) {
+                        result.push_str(value);
+                    }
+                }
+                _ => {
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_alphanumeric() || c == '_' {
+                            name.push(c);
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    if name.is_empty() {
+                        result.push('$');
+                    } else if let Some(value) = self.env.get(&name) {
+                        result.push_str(value);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     pub fn execute_shell_command(&mut self, input: String) {
-        let mut parts = input.trim().split_whitespace();
+        let expanded = self.expand_aliases(input.trim());
+        let expanded = self.expand_variables(&expanded);
 
-        let command = match parts.next() {
-            Some(command) => command,
-            None => return, // skip to next iteration if None
-        };
-        let args = parts;
-        match command {
-            "cd" => {
-                self.handle_cd_command(args);
-                self.shell_history.push(input);
+        if let Some(background_segment) = Self::strip_background_marker(&expanded) {
+            if Self::split_on_connectors(&background_segment).len() > 1 {
+                eprintln!(
+                    "Backgrounding a `&&`/`||` chain isn't supported; background each command separately."
+                );
+                self.record_history(input);
                 return;
             }
-            _ => {}
+            self.run_background(&background_segment, &input);
+            self.record_history(input);
+            return;
+        }
+
+        let mut status = 0;
+        for (connector, segment) in Self::split_on_connectors(&expanded) {
+            let should_run = match connector {
+                None => true,
+                Some(Connector::And) => status == 0,
+                Some(Connector::Or) => status != 0,
+            };
+            if !should_run {
+                continue;
+            }
+            status = self.run_segment(&segment);
+        }
+
+        self.env.insert("status".to_string(), status.to_string());
+        self.record_history(input);
+    }
+
+    /// Runs one `|`-joined pipeline, handling the in-process builtins when the
+    /// pipeline is a single bare command.
+    fn run_segment(&mut self, segment: &str) -> i32 {
+        let pipe_parts = Self::split_on_pipes(segment);
+
+        if pipe_parts.len() == 1 {
+            let mut parts = pipe_parts[0].split_whitespace();
+            match parts.next() {
+                Some("cd") => {
+                    self.handle_cd_command(parts);
+                    return 0;
+                }
+                Some("export") => {
+                    self.handle_export_command(parts);
+                    return 0;
+                }
+                Some("alias") => {
+                    self.handle_alias_command(parts);
+                    return 0;
+                }
+                Some("jobs") => {
+                    self.handle_jobs_command();
+                    return 0;
+                }
+                Some("fg") => {
+                    self.handle_fg_command(parts);
+                    return 0;
+                }
+                Some("bg") => {
+                    self.handle_bg_command(parts);
+                    return 0;
+                }
+                Some("wait") => {
+                    self.handle_wait_command(parts);
+                    return 0;
+                }
+                _ => {}
+            }
         }
-        let mut child = match Command::new(command).args(args).spawn() {
-            Ok(child) => child,
-            Err(e) => {
-                eprintln!("Application error: {e}");
+
+        let commands: Option<Vec<ParsedCommand>> =
+            pipe_parts.iter().map(|part| Self::parse_command(part)).collect();
+        match commands {
+            Some(commands) if !commands.is_empty() => self.run_pipeline(&commands),
+            _ => -1,
+        }
+    }
+
+    /// When `input` ends in a lone backgrounding `&` (not part of an `&&`
+    /// connector), returns the command line it applies to; otherwise `None`.
+    fn strip_background_marker(input: &str) -> Option<String> {
+        let trimmed = input.trim_end();
+        if trimmed.ends_with('&') && !trimmed.ends_with("&&") {
+            Some(trimmed[..trimmed.len() - 1].trim_end().to_string())
+        } else {
+            None
+        }
+    }
+
+    /// Spawns `segment` without waiting on it and records it in the job table.
+    fn run_background(&mut self, segment: &str, original_input: &str) {
+        let pipe_parts = Self::split_on_pipes(segment);
+        let commands: Option<Vec<ParsedCommand>> =
+            pipe_parts.iter().map(|part| Self::parse_command(part)).collect();
+
+        let children = match commands {
+            Some(commands) if !commands.is_empty() => match self.spawn_pipeline(&commands) {
+                Some(children) => children,
+                None => return,
+            },
+            _ => return,
+        };
+
+        if children.is_empty() {
+            return;
+        }
+
+        let id = self.next_job_id;
+        self.next_job_id += 1;
+        println!("[{id}] {}", children.last().unwrap().id());
+        self.jobs.push(Job {
+            id,
+            command: original_input.to_string(),
+            children,
+            status: None,
+        });
+    }
+
+    /// Polls background jobs without blocking, printing a `[id] Done` notice
+    /// the first time a job is observed to have finished. Every stage of the
+    /// pipeline is polled, not just the last one, so earlier stages get
+    /// reaped instead of leaking as zombies; the job's reported status still
+    /// comes from the last stage, matching `run_pipeline`'s foreground
+    /// behavior.
+    pub fn reap_finished_jobs(&mut self) {
+        for job in &mut self.jobs {
+            if job.status.is_some() {
+                continue;
+            }
+            let last = job.children.len() - 1;
+            for (i, child) in job.children.iter_mut().enumerate() {
+                if let Ok(Some(exit)) = child.try_wait() {
+                    if i == last {
+                        job.status = Some(exit.code().unwrap_or(-1));
+                    }
+                }
+            }
+            if job.status.is_some() {
+                println!("[{}] Done", job.id);
+            }
+        }
+    }
+
+    fn handle_jobs_command(&mut self) {
+        self.reap_finished_jobs();
+        for job in &self.jobs {
+            let state = match job.status {
+                Some(code) => format!("Done({code})"),
+                None => "Running".to_string(),
+            };
+            println!("[{}] {} {}", job.id, state, job.command);
+        }
+    }
+
+    fn handle_fg_command(&mut self, args: SplitWhitespace<'_>) {
+        let mut args = args.peekable();
+        let requested: Option<usize> = args.peek().and_then(|s| s.parse().ok());
+        let id = requested.or_else(|| {
+            self.jobs
+                .iter()
+                .rev()
+                .find(|job| job.status.is_none())
+                .map(|job| job.id)
+        });
+
+        let id = match id {
+            Some(id) => id,
+            None => {
+                eprintln!("fg: no current job");
                 return;
             }
         };
-        self.shell_history.push(input);
-        let _ = child.wait();
+
+        match self.jobs.iter().position(|job| job.id == id) {
+            Some(index) => {
+                let job = self.jobs.remove(index);
+                println!("{}", job.command);
+                let status = Self::wait_for_job(job);
+                self.env.insert("status".to_string(), status.to_string());
+            }
+            None => eprintln!("fg: job not found: {id}"),
+        }
+    }
+
+    fn handle_bg_command(&mut self, args: SplitWhitespace<'_>) {
+        let mut args = args.peekable();
+        match args.peek().and_then(|s| s.parse::<usize>().ok()) {
+            Some(id) if self.jobs.iter().any(|job| job.id == id && job.status.is_none()) => {
+                println!("[{id}] continued");
+            }
+            Some(id) => eprintln!("bg: job not found: {id}"),
+            None => eprintln!("bg: job id required"),
+        }
+    }
+
+    fn handle_wait_command(&mut self, args: SplitWhitespace<'_>) {
+        let mut args = args.peekable();
+        let requested: Option<usize> = args.peek().and_then(|s| s.parse().ok());
+
+        let ids: Vec<usize> = match requested {
+            Some(id) => vec![id],
+            None => self
+                .jobs
+                .iter()
+                .filter(|job| job.status.is_none())
+                .map(|job| job.id)
+                .collect(),
+        };
+
+        for id in ids {
+            if let Some(index) = self.jobs.iter().position(|job| job.id == id) {
+                let job = self.jobs.remove(index);
+                let status = Self::wait_for_job(job);
+                self.env.insert("status".to_string(), status.to_string());
+            }
+        }
+    }
+
+    /// Blocks until every stage of `job`'s pipeline has exited, reaping each
+    /// one, and returns the last stage's exit code (matching `run_pipeline`'s
+    /// foreground behavior).
+    fn wait_for_job(mut job: Job) -> i32 {
+        let mut status = -1;
+        for mut child in job.children.drain(..) {
+            status = child.wait().ok().and_then(|s| s.code()).unwrap_or(-1);
+        }
+        status
     }
 
-    pub fn get_shell_history(&self) -> Vec<String> {
-        self.shell_history.clone()
+    /// Splits `input` on top-level `&&` / `||`, skipping anything inside quotes.
+    fn split_on_connectors(input: &str) -> Vec<(Option<Connector>, String)> {
+        let mut result = Vec::new();
+        let mut current = String::new();
+        let mut pending = None;
+        let mut in_quote = None;
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match in_quote {
+                Some(q) if c == q => {
+                    in_quote = None;
+                    current.push(c);
+                }
+                Some(_) => current.push(c),
+                None if c == '\'' || c == '"' => {
+                    in_quote = Some(c);
+                    current.push(c);
+                }
+                None if c == '&' && chars.peek() == Some(&'&') => {
+                    chars.next();
+                    result.push((pending.take(), current.trim().to_string()));
+                    current.clear();
+                    pending = Some(Connector::And);
+                }
+                None if c == '|' && chars.peek() == Some(&'|') => {
+                    chars.next();
+                    result.push((pending.take(), current.trim().to_string()));
+                    current.clear();
+                    pending = Some(Connector::Or);
+                }
+                None => current.push(c),
+            }
+        }
+        result.push((pending.take(), current.trim().to_string()));
+        result.retain(|(_, segment)| !segment.is_empty());
+        result
+    }
+
+    /// Splits a single connector-free segment on top-level `|`, skipping
+    /// anything inside quotes.
+    fn split_on_pipes(input: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut current = String::new();
+        let mut in_quote = None;
+
+        for c in input.chars() {
+            match in_quote {
+                Some(q) if c == q => {
+                    in_quote = None;
+                    current.push(c);
+                }
+                Some(_) => current.push(c),
+                None if c == '\'' || c == '"' => {
+                    in_quote = Some(c);
+                    current.push(c);
+                }
+                None if c == '|' => {
+                    result.push(current.trim().to_string());
+                    current.clear();
+                }
+                None => current.push(c),
+            }
+        }
+        result.push(current.trim().to_string());
+        result
+    }
+
+    /// Tokenizes a command segment, pulling `>`, `>>` and `<` out as
+    /// redirections rather than passing them through as arguments.
+    fn parse_command(segment: &str) -> Option<ParsedCommand> {
+        let tokens = shell_words::split(segment).ok()?;
+        let mut tokens = tokens.into_iter();
+
+        let mut program = None;
+        let mut args = Vec::new();
+        let mut stdin_file = None;
+        let mut stdout_file = None;
+
+        while let Some(token) = tokens.next() {
+            match token.as_str() {
+                ">" => stdout_file = tokens.next().map(|file| (file, false)),
+                ">>" => stdout_file = tokens.next().map(|file| (file, true)),
+                "<" => stdin_file = tokens.next(),
+                _ if program.is_none() => program = Some(token),
+                _ => args.push(token),
+            }
+        }
+
+        Some(ParsedCommand {
+            program: program?,
+            args,
+            stdin_file,
+            stdout_file,
+        })
+    }
+
+    /// Spawns each command in `commands`, wiring child stdout to the next
+    /// child's stdin, and applies any per-command file redirections.
+    /// Returns `None` (after reporting the error) if any stage fails to spawn.
+    fn spawn_pipeline(&mut self, commands: &[ParsedCommand]) -> Option<Vec<Child>> {
+        let mut children = Vec::new();
+        let mut prev_stdout = None;
+        let last = commands.len() - 1;
+
+        for (i, parsed) in commands.iter().enumerate() {
+            let mut command = Command::new(&parsed.program);
+            command.args(&parsed.args);
+
+            if let Some(path) = &parsed.stdin_file {
+                match File::open(path) {
+                    Ok(file) => {
+                        command.stdin(Stdio::from(file));
+                    }
+                    Err(e) => {
+                        eprintln!("Redirection error: {e}");
+                        return None;
+                    }
+                }
+            } else if let Some(stdout) = prev_stdout.take() {
+                command.stdin(Stdio::from(stdout));
+            }
+
+            if let Some((path, append)) = &parsed.stdout_file {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .append(*append)
+                    .truncate(!*append)
+                    .open(path);
+                match file {
+                    Ok(file) => {
+                        command.stdout(Stdio::from(file));
+                    }
+                    Err(e) => {
+                        eprintln!("Redirection error: {e}");
+                        return None;
+                    }
+                }
+            } else if i != last {
+                command.stdout(Stdio::piped());
+            }
+
+            let mut child = match command.spawn() {
+                Ok(child) => child,
+                Err(e) => {
+                    eprintln!("Application error: {e}");
+                    return None;
+                }
+            };
+            prev_stdout = child.stdout.take();
+            children.push(child);
+        }
+
+        Some(children)
+    }
+
+    /// Spawns `commands` as a pipeline and blocks until the last stage exits.
+    fn run_pipeline(&mut self, commands: &[ParsedCommand]) -> i32 {
+        let children = match self.spawn_pipeline(commands) {
+            Some(children) => children,
+            None => return -1,
+        };
+
+        let mut status = 0;
+        for mut child in children {
+            status = child.wait().ok().and_then(|s| s.code()).unwrap_or(-1);
+        }
+        status
     }
 
     fn handle_cd_command(&self, args: SplitWhitespace<'_>) {
@@ -67,4 +589,147 @@ impl ShellCommandExecutor {
             eprintln!("CD error: {e}");
         };
     }
+
+    fn handle_export_command(&mut self, args: SplitWhitespace<'_>) {
+        for arg in args {
+            if let Some((name, value)) = arg.split_once('=') {
+                self.env.insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+
+    fn handle_alias_command(&mut self, args: SplitWhitespace<'_>) {
+        let joined: Vec<&str> = args.collect();
+        let joined = joined.join(" ");
+        if let Some((name, value)) = joined.split_once('=') {
+            let value = value.trim_matches(|c| c == '\'' || c == '"');
+            self.aliases.insert(name.trim().to_string(), value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn executor_with_env(pairs: &[(&str, &str)]) -> ShellCommandExecutor {
+        let mut executor = ShellCommandExecutor::new(&[]);
+        executor.env.clear();
+        for (name, value) in pairs {
+            executor.env.insert(name.to_string(), value.to_string());
+        }
+        executor
+    }
+
+    #[test]
+    fn test_expand_variables_skips_single_quotes() {
+        let executor = executor_with_env(&[("HOME", "/home/user")]);
+        assert_eq!(executor.expand_variables("echo '$HOME'"), "echo '$HOME'");
+        assert_eq!(
+            executor.expand_variables("echo \"$HOME\""),
+            "echo \"/home/user\""
+        );
+        assert_eq!(executor.expand_variables("echo $HOME"), "echo /home/user");
+    }
+
+    #[test]
+    fn test_expand_variables_braces_and_unknown() {
+        let executor = executor_with_env(&[("FOO", "bar")]);
+        assert_eq!(executor.expand_variables("${FOO}baz"), "barbaz");
+        assert_eq!(executor.expand_variables("$MISSING"), "");
+    }
+
+    #[test]
+    fn test_expand_aliases_chains_and_stops_on_cycle() {
+        let mut executor = ShellCommandExecutor::new(&[]);
+        executor.aliases.insert("ll".to_string(), "ls -la".to_string());
+        executor.aliases.insert("a".to_string(), "b".to_string());
+        executor.aliases.insert("b".to_string(), "a".to_string());
+
+        assert_eq!(executor.expand_aliases("ll /tmp"), "ls -la /tmp");
+        // cyclic aliases each expand once, then stop instead of looping forever
+        assert_eq!(executor.expand_aliases("a"), "a");
+    }
+
+    #[test]
+    fn test_split_on_connectors() {
+        let result = ShellCommandExecutor::split_on_connectors("echo a && echo b || echo c");
+        assert_eq!(result.len(), 3);
+        assert!(result[0].0.is_none());
+        assert_eq!(result[0].1, "echo a");
+        assert!(matches!(result[1].0, Some(Connector::And)));
+        assert_eq!(result[1].1, "echo b");
+        assert!(matches!(result[2].0, Some(Connector::Or)));
+        assert_eq!(result[2].1, "echo c");
+    }
+
+    #[test]
+    fn test_split_on_connectors_skips_quoted() {
+        let result = ShellCommandExecutor::split_on_connectors("echo 'a && b'");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].1, "echo 'a && b'");
+    }
+
+    #[test]
+    fn test_split_on_pipes() {
+        let result = ShellCommandExecutor::split_on_pipes("cat file.txt | grep foo | wc -l");
+        assert_eq!(result, vec!["cat file.txt", "grep foo", "wc -l"]);
+    }
+
+    #[test]
+    fn test_split_on_pipes_skips_quoted() {
+        let result = ShellCommandExecutor::split_on_pipes("echo 'a | b'");
+        assert_eq!(result, vec!["echo 'a | b'"]);
+    }
+
+    #[test]
+    fn test_parse_command_redirections() {
+        let parsed = ShellCommandExecutor::parse_command("cat < in.txt > out.txt").unwrap();
+        assert_eq!(parsed.program, "cat");
+        assert!(parsed.args.is_empty());
+        assert_eq!(parsed.stdin_file.as_deref(), Some("in.txt"));
+        assert_eq!(parsed.stdout_file, Some(("out.txt".to_string(), false)));
+    }
+
+    #[test]
+    fn test_parse_command_append_redirection() {
+        let parsed = ShellCommandExecutor::parse_command("echo hi >> out.txt").unwrap();
+        assert_eq!(parsed.program, "echo");
+        assert_eq!(parsed.args, vec!["hi"]);
+        assert_eq!(parsed.stdout_file, Some(("out.txt".to_string(), true)));
+    }
+
+    #[test]
+    fn test_strip_background_marker() {
+        assert_eq!(
+            ShellCommandExecutor::strip_background_marker("sleep 1 &"),
+            Some("sleep 1".to_string())
+        );
+        assert_eq!(
+            ShellCommandExecutor::strip_background_marker("echo a && echo b"),
+            None
+        );
+        assert_eq!(ShellCommandExecutor::strip_background_marker("echo a"), None);
+    }
+
+    #[test]
+    fn test_reap_finished_jobs_marks_finished_pipeline_done() {
+        let mut executor = ShellCommandExecutor::new(&[]);
+        let children = vec![
+            Command::new("true").spawn().unwrap(),
+            Command::new("true").spawn().unwrap(),
+        ];
+        executor.jobs.push(Job {
+            id: 1,
+            command: "true | true".to_string(),
+            children,
+            status: None,
+        });
+
+        // give both stages time to exit before polling
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        executor.reap_finished_jobs();
+
+        assert_eq!(executor.jobs[0].status, Some(0));
+    }
 }
@@ -0,0 +1,191 @@
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, Movement, RepeatCount};
+use std::env;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// How many ranked entries to show per search.
+const MAX_RESULTS: usize = 10;
+
+pub fn default_history_path() -> PathBuf {
+    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    Path::new(&home).join(".config/infrarust/history")
+}
+
+/// Scores `candidate` as a subsequence match of `query`: every character of
+/// `query` must appear in `candidate`, in order (case-insensitively).
+/// Contiguous runs and matches that land on a word boundary score higher.
+/// Returns `None` when `candidate` doesn't contain `query` as a subsequence.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let candidate_chars: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut cursor = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for query_char in query.to_lowercase().chars() {
+        let found = candidate_chars[cursor..]
+            .iter()
+            .position(|&c| c == query_char)
+            .map(|offset| cursor + offset)?;
+
+        score += 1;
+        if previous_match == Some(found.saturating_sub(1)) && found > 0 {
+            score += 5; // contiguous run
+        }
+        let at_word_boundary =
+            found == 0 || matches!(candidate_chars[found - 1], ' ' | '/' | '-' | '_' | '.');
+        if at_word_boundary {
+            score += 3;
+        }
+
+        previous_match = Some(found);
+        cursor = found + 1;
+    }
+
+    Some(score)
+}
+
+/// Ranks `history` by [`fuzzy_score`] against `query`, highest first; ties
+/// keep the most recently used entry first.
+pub fn rank_history<'a>(query: &str, history: &'a [String]) -> Vec<&'a String> {
+    let mut scored: Vec<(&String, i64)> = history
+        .iter()
+        .rev()
+        .filter_map(|entry| fuzzy_score(query, entry).map(|score| (entry, score)))
+        .collect();
+    scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(entry, _)| entry).collect()
+}
+
+/// Bound to Ctrl-R: lets the user narrow `history` with a fuzzy query and
+/// places the selected entry back on the input line. rustyline doesn't give
+/// custom key handlers a way to redraw the line buffer incrementally, so
+/// narrowing happens a query at a time rather than per keystroke.
+pub struct FuzzyHistorySearch {
+    history: Arc<Mutex<Vec<String>>>,
+}
+
+impl FuzzyHistorySearch {
+    pub fn new(history: Arc<Mutex<Vec<String>>>) -> Self {
+        FuzzyHistorySearch { history }
+    }
+}
+
+impl ConditionalEventHandler for FuzzyHistorySearch {
+    fn handle(
+        &self,
+        _evt: &Event,
+        _n: RepeatCount,
+        _positive: bool,
+        ctx: &EventContext,
+    ) -> Option<Cmd> {
+        let history = self.history.lock().unwrap();
+        let query = ctx.line().to_string();
+        with_canonical_mode(|| Self::prompt_loop(&history, query))
+    }
+}
+
+impl FuzzyHistorySearch {
+    fn prompt_loop(history: &[String], mut query: String) -> Option<Cmd> {
+        loop {
+            let matches = rank_history(&query, history);
+
+            println!("\nHistory search: {query}");
+            for (i, entry) in matches.iter().take(MAX_RESULTS).enumerate() {
+                println!("  {}) {}", i + 1, entry);
+            }
+            print!("Select a number, type to refine, or press Enter to cancel: ");
+            let _ = io::stdout().flush();
+
+            let mut input = String::new();
+            if io::stdin().read_line(&mut input).is_err() {
+                return None;
+            }
+            let input = input.trim();
+
+            if input.is_empty() {
+                return None;
+            }
+
+            if let Ok(index) = input.parse::<usize>() {
+                if index >= 1 && index <= matches.len().min(MAX_RESULTS) {
+                    return Some(Cmd::Replace(
+                        Movement::WholeLine,
+                        Some(matches[index - 1].clone()),
+                    ));
+                }
+            }
+
+            query = input.to_string();
+        }
+    }
+}
+
+/// rustyline runs key handlers like [`FuzzyHistorySearch`] while stdin is
+/// still in its own raw mode (no echo, no CR->NL translation), so a plain
+/// `read_line` blocks forever on a bare Enter keypress and the user never
+/// sees what they type. This restores canonical/echo mode and CR->NL
+/// translation for the duration
+/// of `f`, then puts the terminal back exactly as rustyline left it.
+#[cfg(unix)]
+fn with_canonical_mode<T>(f: impl FnOnce() -> T) -> T {
+    use nix::sys::termios::{tcgetattr, tcsetattr, InputFlags, LocalFlags, SetArg};
+    use std::os::fd::BorrowedFd;
+
+    let stdin = unsafe { BorrowedFd::borrow_raw(0) };
+    let original = tcgetattr(stdin).ok();
+    if let Some(original) = &original {
+        let mut canonical = original.clone();
+        canonical.local_flags |= LocalFlags::ICANON | LocalFlags::ECHO;
+        canonical.input_flags |= InputFlags::ICRNL;
+        let _ = tcsetattr(stdin, SetArg::TCSADRAIN, &canonical);
+    }
+
+    let result = f();
+
+    if let Some(original) = &original {
+        let _ = tcsetattr(stdin, SetArg::TCSADRAIN, original);
+    }
+
+    result
+}
+
+#[cfg(not(unix))]
+fn with_canonical_mode<T>(f: impl FnOnce() -> T) -> T {
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_requires_subsequence() {
+        assert!(fuzzy_score("gco", "git checkout").is_some());
+        assert!(fuzzy_score("xyz", "git checkout").is_none());
+        assert!(fuzzy_score("", "anything").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_score_rewards_contiguous_and_boundaries() {
+        let contiguous = fuzzy_score("git", "git status").unwrap();
+        let scattered = fuzzy_score("git", "grep inspect test").unwrap();
+        assert!(contiguous > scattered);
+    }
+
+    #[test]
+    fn test_rank_history_orders_best_match_first() {
+        let history = vec![
+            "cat file.txt".to_string(),
+            "git status".to_string(),
+            "git commit -m wip".to_string(),
+        ];
+        let ranked = rank_history("git", &history);
+        assert_eq!(ranked.len(), 2);
+        assert!(ranked.iter().all(|entry| entry.contains("git")));
+    }
+}
@@ -1,30 +1,40 @@
-use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashSet;
 use std::path::Path;
 use std::process::Command;
 use std::{env, fs};
 
+const BUILTINS: &[&str] = &[
+    "ls", "cd", "pwd", "echo", "export", "source", "alias", "unalias", "history", "jobs", "fg",
+    "bg", "kill", "wait", "exec", "eval", "test", "[", "printf", "read", "set", "unset", "shift",
+    "exit", "return", "break", "continue", "which", "type", "command", "builtin", "declare",
+    "local", "readonly", "true", "false", "git", "mkdir", "rm", "cp", "mv", "cat", "grep", "find",
+    "chmod", "sudo", "apt", "yum", "dnf", "pacman", "brew", "docker", "ssh", "clear",
+];
+
 pub struct ShellCommandDetector {
     available_commands: HashSet<String>,
 }
 
 impl ShellCommandDetector {
-    pub fn new() -> Self {
+    /// `user_aliases` is queried from the shell once at startup (by the
+    /// caller) and handed to every component that needs it, rather than each
+    /// one spawning its own interactive shell to ask.
+    pub fn new(user_aliases: &[(String, String)]) -> Self {
         let mut detector = ShellCommandDetector {
             available_commands: HashSet::new(),
         };
-        detector.load_commands();
+        detector.load_commands(user_aliases);
         detector
     }
 
-    fn load_commands(&mut self) {
+    fn load_commands(&mut self, user_aliases: &[(String, String)]) {
         if let Some(path_env) = env::var_os("PATH") {
             for dir in env::split_paths(&path_env) {
                 self.load_commands_from_directory(&dir);
             }
         }
-        self.load_user_aliases();
+        self.load_user_aliases(user_aliases);
     }
 
     fn load_commands_from_directory(&mut self, dir: &Path) {
@@ -41,30 +51,12 @@ impl ShellCommandDetector {
         }
     }
 
-    fn load_user_aliases(&mut self) {
-        let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
-
-        if let Ok(output) = Command::new(&shell).args(["-i", "-c", "alias"]).output() {
-            if output.status.success() {
-                let aliases = String::from_utf8_lossy(&output.stdout);
-                for line in aliases.lines() {
-                    if let Some(alias) = Self::parse_alias(line) {
-                        self.available_commands.insert(alias);
-                    }
-                }
-            }
+    fn load_user_aliases(&mut self, user_aliases: &[(String, String)]) {
+        for (name, _value) in user_aliases {
+            self.available_commands.insert(name.clone());
         }
     }
 
-    fn parse_alias(line: &str) -> Option<String> {
-        static ALIAS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"alias\s+([^=]+)=").unwrap());
-
-        ALIAS_RE
-            .captures(line)
-            .and_then(|caps| caps.get(1))
-            .map(|m| m.as_str().trim().to_string())
-    }
-
     fn is_obvious_natural_language(&self, text: &str) -> bool {
         let text_lower = text.to_lowercase();
 
@@ -97,19 +89,7 @@ impl ShellCommandDetector {
         }
 
         // Then check builtins
-        let builtins: HashSet<&str> = [
-            "ls", "cd", "pwd", "echo", "export", "source", "alias", "unalias", "history", "jobs",
-            "fg", "bg", "kill", "wait", "exec", "eval", "test", "[", "printf", "read", "set",
-            "unset", "shift", "exit", "return", "break", "continue", "which", "type", "command",
-            "builtin", "declare", "local", "readonly", "true", "false", "git", "mkdir", "rm", "cp",
-            "mv", "cat", "grep", "find", "chmod", "sudo", "apt", "yum", "dnf", "pacman", "brew",
-            "docker", "ssh", "clear",
-        ]
-        .iter()
-        .cloned()
-        .collect();
-
-        if builtins.contains(command) {
+        if BUILTINS.contains(&command) {
             return true;
         }
 
@@ -154,6 +134,13 @@ impl ShellCommandDetector {
                 return false;
             }
 
+            // Pipes, redirection and conditional operators are strong shell
+            // evidence on their own, regardless of what the rest of the line
+            // looks like.
+            if Self::contains_shell_operators(user_input) {
+                return true;
+            }
+
             // Check arguments for natural language patterns
             return self.args_follow_shell_patterns_lenient(user_input, args);
         }
@@ -161,6 +148,23 @@ impl ShellCommandDetector {
         false
     }
 
+    /// Detects `|`, `&&`, `||`, `>`, `>>` and `<` outside of quoted text.
+    fn contains_shell_operators(text: &str) -> bool {
+        let mut in_quote = None;
+
+        for c in text.chars() {
+            match in_quote {
+                Some(q) if c == q => in_quote = None,
+                Some(_) => {}
+                None if c == '\'' || c == '"' => in_quote = Some(c),
+                None if c == '|' || c == '&' || c == '>' || c == '<' => return true,
+                None => {}
+            }
+        }
+
+        false
+    }
+
     fn args_follow_shell_patterns_lenient(
         &self,
         original_input: &str,
@@ -290,6 +294,25 @@ impl ShellCommandDetector {
             .cloned()
             .collect()
     }
+
+    /// Candidates for completing a first-word token: every discovered
+    /// command plus builtin name that starts with `prefix`.
+    pub fn command_completions(&self, prefix: &str) -> Vec<String> {
+        let mut matches: Vec<String> = self
+            .available_commands
+            .iter()
+            .filter(|cmd| cmd.starts_with(prefix))
+            .cloned()
+            .collect();
+
+        for builtin in BUILTINS {
+            if builtin.starts_with(prefix) && !matches.iter().any(|m| m == builtin) {
+                matches.push(builtin.to_string());
+            }
+        }
+
+        matches
+    }
 }
 
 fn is_executable(path: &Path) -> bool {
@@ -315,7 +338,7 @@ mod tests {
 
     #[test]
     fn test_is_shell_command() {
-        let mut detector = ShellCommandDetector::new();
+        let mut detector = ShellCommandDetector::new(&[]);
 
         // Shell commands
         assert!(detector.is_shell_command("ls -la"));
@@ -336,11 +359,28 @@ mod tests {
         // Commands with quoted arguments
         assert!(detector.is_shell_command("grep 'search pattern' file.txt"));
         assert!(detector.is_shell_command("echo \"hello world\""));
+
+        // Pipelines, redirection and conditional operators
+        assert!(detector.is_shell_command("cat file.txt | grep pattern"));
+        assert!(detector.is_shell_command("ls -la > output.txt"));
+        assert!(detector.is_shell_command("git pull && git status"));
+        assert!(detector.is_shell_command("git pull || echo failed"));
+    }
+
+    #[test]
+    fn test_contains_shell_operators() {
+        assert!(ShellCommandDetector::contains_shell_operators("a | b"));
+        assert!(ShellCommandDetector::contains_shell_operators("a && b"));
+        assert!(ShellCommandDetector::contains_shell_operators("a || b"));
+        assert!(ShellCommandDetector::contains_shell_operators("a > b"));
+        assert!(ShellCommandDetector::contains_shell_operators("a < b"));
+        assert!(!ShellCommandDetector::contains_shell_operators("echo 'a | b'"));
+        assert!(!ShellCommandDetector::contains_shell_operators("ls -la"));
     }
 
     #[test]
     fn test_natural_language_detection() {
-        let detector = ShellCommandDetector::new();
+        let detector = ShellCommandDetector::new(&[]);
 
         // Natural language patterns
         assert!(!detector.check_natural_language_patterns("better than the other command"));
@@ -356,7 +396,7 @@ mod tests {
 
     #[test]
     fn test_extract_unquoted_parts() {
-        let detector = ShellCommandDetector::new();
+        let detector = ShellCommandDetector::new(&[]);
 
         assert_eq!(
             detector.extract_unquoted_parts("command 'quoted arg' unquoted"),
@@ -372,4 +412,15 @@ mod tests {
             "unquoted"
         );
     }
+
+    #[test]
+    fn test_command_completions() {
+        let detector = ShellCommandDetector::new(&[]);
+
+        let matches = detector.command_completions("ech");
+        assert!(matches.contains(&"echo".to_string()));
+
+        let matches = detector.command_completions("gi");
+        assert!(matches.contains(&"git".to_string()));
+    }
 }
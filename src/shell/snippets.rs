@@ -0,0 +1,309 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use std::collections::{BTreeMap, HashSet};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A `<name>` or `<name: suggestion command>` token inside a snippet's template.
+pub struct Placeholder {
+    pub name: String,
+    pub suggestion_command: Option<String>,
+}
+
+/// A named, parameterized one-liner loaded from the snippet config file.
+pub struct Snippet {
+    pub name: String,
+    pub description: Option<String>,
+    pub template: String,
+}
+
+pub struct SnippetLibrary {
+    snippets: Vec<Snippet>,
+}
+
+impl SnippetLibrary {
+    /// Loads snippets from `~/.config/infrarust/snippets.conf`, or an empty
+    /// library if the file doesn't exist.
+    pub fn load_default() -> Self {
+        Self::load_from_path(&Self::default_path())
+    }
+
+    pub fn load_from_path(path: &Path) -> Self {
+        let snippets = fs::read_to_string(path)
+            .map(|contents| Self::parse(&contents))
+            .unwrap_or_default();
+        SnippetLibrary { snippets }
+    }
+
+    fn default_path() -> PathBuf {
+        let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        Path::new(&home).join(".config/infrarust/snippets.conf")
+    }
+
+    /// Parses `[name]` sections with `description = ...` and `command = ...`
+    /// keys, e.g.:
+    ///
+    /// ```text
+    /// [find-large-files]
+    /// description = Find files larger than a given size
+    /// command = find <dir> -size +<size>M
+    /// ```
+    fn parse(contents: &str) -> Vec<Snippet> {
+        let mut snippets = Vec::new();
+        let mut name = None;
+        let mut description = None;
+        let mut command = None;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(section) = line.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+                if let (Some(name), Some(command)) = (name.take(), command.take()) {
+                    snippets.push(Snippet {
+                        name,
+                        description: description.take(),
+                        template: command,
+                    });
+                }
+                name = Some(section.to_string());
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                match key.trim() {
+                    "description" => description = Some(value.trim().to_string()),
+                    "command" => command = Some(value.trim().to_string()),
+                    _ => {}
+                }
+            }
+        }
+
+        if let (Some(name), Some(command)) = (name, command) {
+            snippets.push(Snippet {
+                name,
+                description,
+                template: command,
+            });
+        }
+
+        snippets
+    }
+
+    /// Ranks snippets by how many words their description shares with
+    /// `query`, most matching first. Snippets with no overlap are dropped.
+    pub fn find_matches(&self, query: &str) -> Vec<&Snippet> {
+        let query_lower = query.to_lowercase();
+        let query_words: HashSet<&str> = query_lower.split_whitespace().collect();
+
+        let mut scored: Vec<(&Snippet, usize)> = self
+            .snippets
+            .iter()
+            .filter_map(|snippet| {
+                let description = snippet
+                    .description
+                    .as_deref()
+                    .unwrap_or(&snippet.name)
+                    .to_lowercase();
+                let score = description
+                    .split_whitespace()
+                    .filter(|word| query_words.contains(word))
+                    .count();
+                if score > 0 {
+                    Some((snippet, score))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        scored.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(snippet, _)| snippet).collect()
+    }
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static PLACEHOLDER_RE: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"<([^:>]+)(?::\s*([^>]+))?>").unwrap());
+    &PLACEHOLDER_RE
+}
+
+/// Collects every distinct placeholder in `template`, in the order it first
+/// appears.
+fn extract_placeholders(template: &str) -> Vec<Placeholder> {
+    let mut seen = HashSet::new();
+    let mut placeholders = Vec::new();
+
+    for caps in placeholder_regex().captures_iter(template) {
+        let name = caps.get(1).unwrap().as_str().trim().to_string();
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        let suggestion_command = caps.get(2).map(|m| m.as_str().trim().to_string());
+        placeholders.push(Placeholder {
+            name,
+            suggestion_command,
+        });
+    }
+
+    placeholders
+}
+
+fn substitute_placeholders(template: &str, values: &BTreeMap<String, String>) -> String {
+    placeholder_regex()
+        .replace_all(template, |caps: &Captures| {
+            let name = caps.get(1).unwrap().as_str().trim();
+            values.get(name).cloned().unwrap_or_default()
+        })
+        .to_string()
+}
+
+/// Runs a placeholder's suggestion command through the user's shell and
+/// returns its stdout lines as selectable default values.
+fn run_suggestion_command(command: &str) -> Vec<String> {
+    let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
+    match Command::new(&shell).args(["-c", command]).output() {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn prompt_for_placeholder(placeholder: &Placeholder) -> String {
+    let suggestions = placeholder
+        .suggestion_command
+        .as_deref()
+        .map(run_suggestion_command)
+        .unwrap_or_default();
+
+    if suggestions.is_empty() {
+        print!("{}: ", placeholder.name);
+    } else {
+        println!("{}:", placeholder.name);
+        for (i, suggestion) in suggestions.iter().enumerate() {
+            println!("  {}) {}", i + 1, suggestion);
+        }
+        print!("Select a number or type a value: ");
+    }
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return String::new();
+    }
+    let input = input.trim();
+
+    if let Ok(index) = input.parse::<usize>() {
+        if index >= 1 && index <= suggestions.len() {
+            return suggestions[index - 1].clone();
+        }
+    }
+    input.to_string()
+}
+
+/// Prompts once per distinct placeholder and substitutes the entered values
+/// back into the snippet's template.
+pub fn resolve_snippet(snippet: &Snippet) -> String {
+    let mut values = BTreeMap::new();
+    for placeholder in extract_placeholders(&snippet.template) {
+        let value = prompt_for_placeholder(&placeholder);
+        values.insert(placeholder.name, value);
+    }
+    substitute_placeholders(&snippet.template, &values)
+}
+
+/// Fuzzy-matches `query` against the library's snippet descriptions and, if
+/// any match, lets the user pick one.
+pub fn select_snippet<'a>(library: &'a SnippetLibrary, query: &str) -> Option<&'a Snippet> {
+    let matches = library.find_matches(query);
+    if matches.is_empty() {
+        return None;
+    }
+
+    println!("Did you mean one of these snippets?");
+    for (i, snippet) in matches.iter().enumerate() {
+        let description = snippet.description.as_deref().unwrap_or("");
+        println!("  {}) {} - {}", i + 1, snippet.name, description);
+    }
+    print!("Select a number (or press Enter to skip): ");
+    let _ = io::stdout().flush();
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return None;
+    }
+
+    match input.trim().parse::<usize>() {
+        Ok(choice) if choice >= 1 && choice <= matches.len() => Some(matches[choice - 1]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snippets() {
+        let contents = "\
+[find-large-files]
+description = Find files larger than a given size
+command = find <dir> -size +<size>M
+
+[grep-text]
+description = Search for a pattern in text files
+command = grep -r '<pattern>' <dir: ls -d */>
+";
+        let snippets = SnippetLibrary::parse(contents);
+        assert_eq!(snippets.len(), 2);
+        assert_eq!(snippets[0].name, "find-large-files");
+        assert_eq!(snippets[1].template, "grep -r '<pattern>' <dir: ls -d */>");
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let library = SnippetLibrary {
+            snippets: SnippetLibrary::parse(
+                "[find-large-files]\ndescription = find large files by size\ncommand = find <dir> -size +<size>M\n",
+            ),
+        };
+
+        assert_eq!(library.find_matches("find large files").len(), 1);
+        assert!(library.find_matches("what is the weather").is_empty());
+    }
+
+    #[test]
+    fn test_extract_and_substitute_placeholders() {
+        let template = "find <dir> -name '<pattern>'";
+        let placeholders = extract_placeholders(template);
+        assert_eq!(placeholders.len(), 2);
+        assert_eq!(placeholders[0].name, "dir");
+        assert_eq!(placeholders[1].name, "pattern");
+
+        let mut values = BTreeMap::new();
+        values.insert("dir".to_string(), ".".to_string());
+        values.insert("pattern".to_string(), "*.rs".to_string());
+        assert_eq!(
+            substitute_placeholders(template, &values),
+            "find . -name '*.rs'"
+        );
+    }
+
+    #[test]
+    fn test_extract_placeholder_with_suggestion() {
+        let placeholders = extract_placeholders("ls <dir: ls -d */>");
+        assert_eq!(placeholders.len(), 1);
+        assert_eq!(placeholders[0].name, "dir");
+        assert_eq!(
+            placeholders[0].suggestion_command.as_deref(),
+            Some("ls -d */")
+        );
+    }
+}